@@ -10,6 +10,8 @@ use alloc::vec::Vec;
 
 use num_traits::{Float, FromPrimitive};
 use rolling_stats::Stats;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 const DEFAULT_BAR_CHAR: &str = "â–‘";
 const DEFAULT_PRECISION: usize = 2;
@@ -22,7 +24,17 @@ const DEFAULT_PRECISION: usize = 2;
 ///
 /// The struct includes fields for managing the histogram bins, underflow,
 /// overflow, and other statistics.
+///
+/// With the `serde` feature enabled, `Hstats` (and the embedded
+/// `rolling_stats::Stats`, which must be built with its own `serde` feature)
+/// implement `Serialize`/`Deserialize`, so partial histograms computed on
+/// worker nodes can be shipped to an aggregator and `merge`d there.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))
+)]
 pub struct Hstats<T>
 where
     T: Float + AddAssign + FromPrimitive + Debug + Display,
@@ -30,19 +42,168 @@ where
     start: T,
     end: T,
     bin_count: usize,
-    bin_width: T,
+    edges: Vec<T>,
     bins: Vec<u64>,
     underflow: u64,
     overflow: u64,
     stats: Stats<T>,
     precision: usize,
     bar_char: String,
+    // Set by `with_buckets`: rescale the range instead of over/underflowing.
+    auto_range: bool,
 }
 
 impl<T> Hstats<T>
 where
     T: Float + AddAssign + FromPrimitive + Debug + Display,
 {
+    /// Builds a histogram from a vector of N+1 strictly increasing bin edges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `edges` has fewer than 2 values or if the values aren't
+    /// strictly increasing.
+    fn from_edges(edges: Vec<T>) -> Self {
+        assert!(
+            edges.len() >= 2,
+            "edges must contain at least 2 values to define at least 1 bin"
+        );
+        for w in edges.windows(2) {
+            assert!(
+                w[0] < w[1],
+                "edges must be strictly increasing ({} >= {})",
+                w[0],
+                w[1]
+            );
+        }
+
+        let bin_count = edges.len() - 1;
+
+        Self {
+            start: edges[0],
+            end: edges[bin_count],
+            bin_count,
+            edges,
+            bins: vec![0; bin_count],
+            underflow: 0,
+            overflow: 0,
+            stats: Stats::new(),
+            precision: DEFAULT_PRECISION,
+            bar_char: DEFAULT_BAR_CHAR.to_string(),
+            auto_range: false,
+        }
+    }
+
+    /// Rebuilds the histogram bins over `[start, end)`, keeping `bin_count` fixed
+    /// and redistributing the existing counts across the new bins proportionally
+    /// to how much of each old bin's range overlaps each new bin.
+    ///
+    /// This is an approximation: counts that land partway across a new bin
+    /// boundary are split by the fraction of their old bin's width that falls
+    /// on each side, then rounded, so repeated rescaling can introduce a small
+    /// amount of binning error.
+    fn rescale_to(&mut self, start: T, end: T) {
+        let bin_count_t = T::from(self.bin_count).unwrap();
+        let width = (end - start) / bin_count_t;
+
+        let mut new_edges: Vec<T> = (0..self.bin_count)
+            .map(|i| start + width * T::from(i).unwrap())
+            .collect();
+        new_edges.push(end);
+
+        self.bins = self.redistribute(&new_edges);
+        self.start = start;
+        self.end = end;
+        self.edges = new_edges;
+    }
+
+    /// Distributes this histogram's bin counts across `new_edges`, splitting each
+    /// old bin's count proportionally to how much of its range overlaps each new
+    /// bin.
+    ///
+    /// Each old bin's count is allocated with the largest-remainder method
+    /// (take the integer floor of every overlapping share, then hand out the
+    /// few leftover units to the shares with the largest fractional part) so
+    /// that `new_bins` always sums to the same total as `self.bins`, instead
+    /// of letting independent rounding of each share drift the total count.
+    fn redistribute(&self, new_edges: &[T]) -> Vec<u64> {
+        let mut new_bins = vec![0u64; new_edges.len() - 1];
+
+        for (i, &count) in self.bins.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+
+            let (lower, upper) = (self.edges[i], self.edges[i + 1]);
+            let width = upper - lower;
+            let count_t = T::from(count).unwrap();
+
+            // The exact (real-valued) share of `count` overlapping each new bin.
+            let mut shares: Vec<(usize, T)> = Vec::new();
+            for j in 0..new_bins.len() {
+                let (new_lower, new_upper) = (new_edges[j], new_edges[j + 1]);
+                let overlap_lower = if lower > new_lower { lower } else { new_lower };
+                let overlap_upper = if upper < new_upper { upper } else { new_upper };
+
+                if overlap_upper > overlap_lower {
+                    let fraction = (overlap_upper - overlap_lower) / width;
+                    shares.push((j, count_t * fraction));
+                }
+            }
+
+            let mut allocated = 0u64;
+            let mut remainders: Vec<(usize, T)> = Vec::with_capacity(shares.len());
+            for (j, share) in shares {
+                let floor = share.floor().to_u64().unwrap();
+                new_bins[j] += floor;
+                allocated += floor;
+                remainders.push((j, share - T::from(floor).unwrap()));
+            }
+
+            // Hand out the leftover units (count - allocated, always small)
+            // to the bins with the largest fractional remainder first.
+            remainders.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            let mut leftover = count - allocated;
+            for (j, _) in remainders {
+                if leftover == 0 {
+                    break;
+                }
+                new_bins[j] += 1;
+                leftover -= 1;
+            }
+        }
+
+        new_bins
+    }
+
+    /// Doubles the range (toward whichever side is needed) until `value` is
+    /// inside it, then rescales the bins into the new range. Used by
+    /// auto-ranging histograms created with [`Hstats::with_buckets`].
+    fn rescale_to_include(&mut self, value: T) {
+        let bin_count_t = T::from(self.bin_count).unwrap();
+        let two = T::from(2).unwrap();
+
+        let (start, end) = if value < self.start {
+            let mut start = self.start;
+            let mut width = (self.end - self.start) / bin_count_t;
+            while value < start {
+                width = width * two;
+                start = self.end - width * bin_count_t;
+            }
+            (start, self.end)
+        } else {
+            let mut end = self.end;
+            let mut width = (self.end - self.start) / bin_count_t;
+            while value >= end {
+                width = width * two;
+                end = self.start + width * bin_count_t;
+            }
+            (self.start, end)
+        };
+
+        self.rescale_to(start, end);
+    }
+
     /// Constructs a new `Hstats` instance with specified start and end points and bin count.
     ///
     /// # Arguments
@@ -63,18 +224,130 @@ where
 
         let bin_width = (end - start) / T::from(bin_count).unwrap();
 
-        Self {
-            start,
-            end,
-            bin_count,
-            bin_width,
-            bins: vec![0; bin_count],
-            underflow: 0,
-            overflow: 0,
-            stats: Stats::new(),
-            precision: DEFAULT_PRECISION,
-            bar_char: DEFAULT_BAR_CHAR.to_string(),
+        // Edges are derived from `start` and `bin_width` except for the last
+        // one, which is pinned to `end` so rounding error can't shift it.
+        let mut edges: Vec<T> = (0..bin_count)
+            .map(|i| start + bin_width * T::from(i).unwrap())
+            .collect();
+        edges.push(end);
+
+        Self::from_edges(edges)
+    }
+
+    /// Constructs a new `Hstats` instance from explicit, variable-width bin edges.
+    ///
+    /// `edges` must yield N+1 monotonically increasing values defining N bins,
+    /// e.g. `[0., 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]` defines 6 bins of unequal width.
+    /// This is useful for log-spaced or domain-specific binning that uniform
+    /// histograms can't express.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than 2 edges are given or if the edges aren't strictly
+    /// increasing.
+    pub fn from_ranges(edges: impl IntoIterator<Item = T>) -> Self {
+        Self::from_edges(edges.into_iter().collect())
+    }
+
+    /// Constructs an HDR-style logarithmic histogram covering `[lowest_discernible, highest]`.
+    ///
+    /// Buckets are laid out geometrically: each "magnitude" covers a power-of-two-wide
+    /// range (`[lowest_discernible * 2^i, lowest_discernible * 2^(i+1))`) subdivided into
+    /// linear sub-buckets, enough of them to distinguish `sig_figs` decimal digits
+    /// (`2 * 10^sig_figs` sub-buckets, rounded up to a power of two). This keeps the
+    /// relative error below `10^-sig_figs` across the whole range, which uniform bins
+    /// can't do for data spanning many orders of magnitude (e.g. latencies or sizes).
+    ///
+    /// The resulting range may extend past `highest` by up to a factor of two, since
+    /// magnitudes double and the last one is only required to cover `highest`, not
+    /// end exactly on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lowest_discernible` isn't greater than 0, if `highest` isn't
+    /// greater than `lowest_discernible`, if `sig_figs` is too large for the
+    /// sub-bucket count to fit in a `u64`, or if `highest / lowest_discernible`
+    /// spans so many magnitudes that they can't be represented as a power-of-two
+    /// multiplier of `lowest_discernible`.
+    pub fn log_scale(lowest_discernible: T, highest: T, sig_figs: u8) -> Self {
+        assert!(
+            lowest_discernible > T::zero(),
+            "lowest_discernible ({lowest_discernible}) must be greater than 0"
+        );
+        assert!(
+            highest > lowest_discernible,
+            "highest ({highest}) must be greater than lowest_discernible ({lowest_discernible})"
+        );
+        assert!(
+            sig_figs <= 18,
+            "sig_figs ({sig_figs}) must be at most 18, or the required sub-bucket count overflows a u64"
+        );
+
+        // Enough linear sub-buckets per magnitude to distinguish `sig_figs` decimal
+        // digits, rounded up to a power of two.
+        let required_sub_buckets = 2.0 * 10f64.powi(sig_figs as i32);
+        let mut sub_buckets = 1u64;
+        while (sub_buckets as f64) < required_sub_buckets {
+            sub_buckets = sub_buckets
+                .checked_mul(2)
+                .expect("sig_figs produces a sub-bucket count that overflows a u64");
+        }
+        let sub_buckets = sub_buckets as usize;
+
+        let lowest_f = lowest_discernible.to_f64().unwrap();
+        let highest_f = highest.to_f64().unwrap();
+        let mut magnitudes = (highest_f / lowest_f).log2().ceil() as u32;
+        // Bins are half-open, so `highest` must lie below the last magnitude's upper edge.
+        if lowest_f * 2f64.powi(magnitudes as i32) <= highest_f {
+            magnitudes += 1;
+        }
+        assert!(
+            magnitudes < u64::BITS,
+            "highest ({highest}) / lowest_discernible ({lowest_discernible}) spans too many magnitudes ({magnitudes}) to represent as a power-of-two multiplier"
+        );
+
+        let mut edges = Vec::with_capacity(magnitudes as usize * sub_buckets + 1);
+        for magnitude in 0..magnitudes {
+            let magnitude_start = lowest_discernible * T::from(1u64 << magnitude).unwrap();
+            let magnitude_width = magnitude_start / T::from(sub_buckets).unwrap();
+            for sub_bucket in 0..sub_buckets {
+                edges.push(magnitude_start + magnitude_width * T::from(sub_bucket).unwrap());
+            }
         }
+        edges.push(lowest_discernible * T::from(1u64 << magnitudes).unwrap());
+
+        Self::from_edges(edges)
+    }
+
+    /// Constructs an auto-ranging histogram that doesn't require `start`/`end` up
+    /// front, matching the ergonomics of `histo_fp`.
+    ///
+    /// The histogram holds a fixed number of buckets and starts with a tiny
+    /// placeholder range. As soon as a value falls outside the current range,
+    /// the range is doubled toward whichever side is needed and the existing
+    /// counts are redistributed proportionally across the resized bins, so
+    /// `bin_count` never changes. This avoids the common failure mode of
+    /// guessing `start`/`end` up front and having real data land in the
+    /// under/overflow buckets.
+    ///
+    /// Because independently-grown instances can end up with different bin
+    /// edges, [`Hstats::merge`] rescales both operands to their combined range
+    /// before combining bins, so merging auto-ranging histograms remains
+    /// well-defined (at the cost of the same rescaling approximation).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bin_count` is 0.
+    pub fn with_buckets(bin_count: usize, precision: usize) -> Self {
+        assert!(
+            bin_count > 0,
+            "bin_count ({bin_count}) must be greater than 0"
+        );
+
+        let mut hstats = Self::new(T::zero(), T::one(), bin_count);
+        hstats.auto_range = true;
+        hstats.precision = precision;
+        hstats
     }
 
     /// Adds a value to the histogram and updates the statistics.
@@ -85,16 +358,28 @@ where
     pub fn add(&mut self, value: T) {
         self.stats.update(value);
 
+        if self.auto_range && (value < self.start || value >= self.end) {
+            self.rescale_to_include(value);
+        }
+
         if value < self.start {
             self.underflow += 1;
         } else if value >= self.end {
             self.overflow += 1;
         } else {
-            let index = ((value - self.start) / self.bin_width)
-                .floor()
-                .to_usize()
-                .unwrap();
-            self.bins[index] += 1;
+            // Binary search the edges for the bin whose lower edge is the
+            // greatest one still <= `value`.
+            let mut lo = 0usize;
+            let mut hi = self.edges.len() - 1;
+            while lo + 1 < hi {
+                let mid = lo + (hi - lo) / 2;
+                if self.edges[mid] <= value {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            self.bins[lo] += 1;
         }
     }
 
@@ -110,13 +395,44 @@ where
     ///
     /// # Panics
     ///
-    /// Panics if the `start`, `end`, and `bin_count` of the two histograms aren't equal.
+    /// Panics if the bin edges of the two histograms aren't equal, unless both
+    /// are auto-ranging histograms (see [`Hstats::with_buckets`]) with the same
+    /// `bin_count`, in which case both are first rescaled to their combined
+    /// range.
     pub fn merge(&self, other: &Self) -> Self {
-        assert_eq!(self.start, other.start, "Starts must be equal");
-        assert_eq!(self.end, other.end, "Ends must be equal");
-        assert_eq!(self.bin_count, other.bin_count, "Bin counts must be equal");
+        if self.edges != other.edges {
+            assert!(
+                self.auto_range && other.auto_range,
+                "Bin edges must be equal"
+            );
+            assert_eq!(
+                self.bin_count, other.bin_count,
+                "Bin counts must be equal to merge auto-ranging histograms"
+            );
+
+            let start = if self.start < other.start {
+                self.start
+            } else {
+                other.start
+            };
+            let end = if self.end > other.end {
+                self.end
+            } else {
+                other.end
+            };
+
+            let mut a = self.clone();
+            a.rescale_to(start, end);
+            let mut b = other.clone();
+            b.rescale_to(start, end);
+
+            return a.merge(&b);
+        }
 
-        let mut merged = Hstats::new(self.start, self.end, self.bin_count);
+        let mut merged = Hstats::from_edges(self.edges.clone());
+        merged.auto_range = self.auto_range && other.auto_range;
+        merged.precision = self.precision;
+        merged.bar_char = self.bar_char.clone();
 
         // Add the underflow and overflow together
         merged.underflow = self.underflow + other.underflow;
@@ -139,10 +455,13 @@ where
         self.bin_count
     }
 
-    /// Returns the width of each bin in the histogram.
+    /// Returns the average width of the bins in the histogram.
     /// Same as `(end - start) / bin_count`.
+    ///
+    /// For histograms built with `from_ranges`, bins aren't necessarily all
+    /// the same width; use [`Hstats::bins`] to get the edges of each bin.
     pub fn bin_width(&self) -> T {
-        self.bin_width
+        (self.end - self.start) / T::from(self.bin_count).unwrap()
     }
 
     /// Returns the start of the range for the histogram bins.
@@ -171,13 +490,8 @@ where
         bins.push((T::neg_infinity(), self.start, self.underflow));
 
         // From the start of the first bin to the end of the last bin
-        let mut lower = self.start;
-        let mut upper = self.start + self.bin_width;
-
-        for count in &self.bins {
-            bins.push((lower, upper, *count));
-            lower = upper;
-            upper += self.bin_width;
+        for (i, count) in self.bins.iter().enumerate() {
+            bins.push((self.edges[i], self.edges[i + 1], *count));
         }
 
         // From the end of the last bin to positive infinity
@@ -186,6 +500,34 @@ where
         bins
     }
 
+    /// Returns the width of each bin, in the same order as [`Hstats::bins`]
+    /// (underflow, then each bin, then overflow). The underflow and overflow
+    /// buckets have infinite width.
+    pub fn widths(&self) -> impl Iterator<Item = T> + '_ {
+        core::iter::once(T::infinity())
+            .chain(self.edges.windows(2).map(|w| w[1] - w[0]))
+            .chain(core::iter::once(T::infinity()))
+    }
+
+    /// Returns each bin's count divided by its width, a probability-density
+    /// estimate that corrects for the non-uniform bin widths supported by
+    /// [`Hstats::from_ranges`] and [`Hstats::log_scale`]. Yielded in the same
+    /// order as [`Hstats::bins`]. The underflow and overflow buckets have
+    /// infinite width, so their density is reported as zero.
+    pub fn normalized_bins(&self) -> impl Iterator<Item = T> + '_ {
+        let counts = core::iter::once(self.underflow)
+            .chain(self.bins.iter().copied())
+            .chain(core::iter::once(self.overflow));
+
+        counts.zip(self.widths()).map(|(count, width)| {
+            if width.is_infinite() {
+                T::zero()
+            } else {
+                T::from(count).unwrap() / width
+            }
+        })
+    }
+
     /// Maximum value seen so far.
     pub fn max(&self) -> T {
         self.stats.max
@@ -211,6 +553,78 @@ where
         self.stats.count
     }
 
+    /// Estimates the value at quantile `q` (in `[0, 1]`) from the accumulated bins.
+    ///
+    /// This walks the bins accumulating counts until the target rank is reached,
+    /// then linearly interpolates the value within that bin. Because raw samples
+    /// aren't retained, the result is an approximation whose error is bounded by
+    /// the width of the containing bin.
+    ///
+    /// If the target rank falls inside the underflow or overflow bucket the exact
+    /// value is unknown, so `start()` or `end()` is returned respectively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is outside `[0, 1]`.
+    pub fn quantile(&self, q: f64) -> T {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "q ({q}) must be in the range [0, 1]"
+        );
+
+        let n = self.count() as f64;
+        if n == 0.0 {
+            return self.start;
+        }
+
+        let rank = q * n;
+        let underflow = self.underflow as f64;
+
+        if rank <= underflow {
+            return self.start;
+        }
+
+        let mut cumulative = underflow;
+        for (i, &count) in self.bins.iter().enumerate() {
+            let count = count as f64;
+            if rank <= cumulative + count {
+                let lower = self.edges[i];
+                if count == 0.0 {
+                    return lower;
+                }
+                let bin_width = self.edges[i + 1] - lower;
+                let fraction = T::from((rank - cumulative) / count).unwrap();
+                return lower + fraction * bin_width;
+            }
+            cumulative += count;
+        }
+
+        // The target rank falls inside the overflow bucket, whose upper bound is unbounded.
+        self.end
+    }
+
+    /// Estimates the value at percentile `p` (in `[0, 100]`) from the accumulated bins.
+    ///
+    /// See [`Hstats::quantile`] for the approximation used and its edge cases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is outside `[0, 100]`.
+    pub fn percentile(&self, p: f64) -> T {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "p ({p}) must be in the range [0, 100]"
+        );
+        self.quantile(p / 100.0)
+    }
+
+    /// Estimates the median (the 50th percentile) from the accumulated bins.
+    ///
+    /// See [`Hstats::quantile`] for the approximation used and its edge cases.
+    pub fn median(&self) -> T {
+        self.quantile(0.5)
+    }
+
     /// Modifies the precision of the histogram.
     pub fn with_precision(mut self, precision: usize) -> Self {
         self.precision = precision;
@@ -296,7 +710,11 @@ mod tests {
         assert_eq!(hstats.start, 0.0);
         assert_eq!(hstats.end, 10.0);
         assert_eq!(hstats.bin_count, 10);
-        assert_eq!(hstats.bin_width, 1.0);
+        assert_eq!(hstats.bin_width(), 1.0);
+        assert_eq!(
+            hstats.edges,
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]
+        );
         assert_eq!(hstats.bins.len(), 10);
     }
 
@@ -357,7 +775,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Starts must be equal")]
+    #[should_panic(expected = "Bin edges must be equal")]
     fn test_merge_different_start() {
         let hstats1 = Hstats::new(0.0, 10.0, 10);
         let hstats2 = Hstats::new(1.0, 10.0, 10);
@@ -366,7 +784,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Ends must be equal")]
+    #[should_panic(expected = "Bin edges must be equal")]
     fn test_merge_different_end() {
         let hstats1 = Hstats::new(0.0, 10.0, 10);
         let hstats2 = Hstats::new(0.0, 11.0, 10);
@@ -375,7 +793,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Bin counts must be equal")]
+    #[should_panic(expected = "Bin edges must be equal")]
     fn test_merge_different_bin_count() {
         let hstats1 = Hstats::new(0.0, 10.0, 10);
         let hstats2 = Hstats::new(0.0, 10.0, 11);
@@ -383,6 +801,77 @@ mod tests {
         let _ = hstats1.merge(&hstats2);
     }
 
+    // Tests for Hstats::from_ranges
+    #[test]
+    fn test_from_ranges() {
+        let hstats = Hstats::from_ranges(vec![0.0, 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]);
+
+        assert_eq!(hstats.start, 0.0);
+        assert_eq!(hstats.end, 2.0);
+        assert_eq!(hstats.bin_count, 6);
+        assert_eq!(hstats.bins.len(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "edges must contain at least 2 values")]
+    fn test_from_ranges_too_few_edges() {
+        let _ = Hstats::from_ranges(vec![0.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "edges must be strictly increasing")]
+    fn test_from_ranges_not_increasing() {
+        let _ = Hstats::from_ranges(vec![0.0, 0.5, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_from_ranges_add() {
+        let mut hstats = Hstats::from_ranges(vec![0.0, 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]);
+
+        hstats.add(0.05); // bin 0: [0.0, 0.1)
+        hstats.add(0.15); // bin 1: [0.1, 0.2)
+        hstats.add(0.6); // bin 3: [0.5, 0.7)
+        hstats.add(1.5); // bin 5: [1.0, 2.0)
+        hstats.add(-1.0); // underflow
+        hstats.add(2.0); // overflow
+
+        assert_eq!(hstats.bins, vec![1, 1, 0, 1, 0, 1]);
+        assert_eq!(hstats.underflow, 1);
+        assert_eq!(hstats.overflow, 1);
+        assert_eq!(hstats.count(), 6);
+    }
+
+    #[test]
+    fn test_from_ranges_bins() {
+        let hstats = Hstats::from_ranges(vec![0.0, 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]);
+
+        let bins = hstats.bins();
+        assert_eq!(bins[1], (0.0, 0.1, 0));
+        assert_eq!(bins[3], (0.2, 0.5, 0));
+        assert_eq!(bins[6], (1.0, 2.0, 0));
+    }
+
+    #[test]
+    fn test_from_ranges_merge() {
+        let mut hstats1 = Hstats::from_ranges(vec![0.0, 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]);
+        hstats1.add(0.05);
+        let mut hstats2 = Hstats::from_ranges(vec![0.0, 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]);
+        hstats2.add(0.15);
+
+        let merged = hstats1.merge(&hstats2);
+        assert_eq!(merged.bins, vec![1, 1, 0, 0, 0, 0]);
+        assert_eq!(merged.count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bin edges must be equal")]
+    fn test_from_ranges_merge_different_edges() {
+        let hstats1 = Hstats::from_ranges(vec![0.0, 0.1, 0.2]);
+        let hstats2 = Hstats::from_ranges(vec![0.0, 0.2, 0.4]);
+
+        let _ = hstats1.merge(&hstats2);
+    }
+
     #[test]
     fn stats_for_large_random_data() {
         type T = f64;
@@ -477,4 +966,294 @@ mod tests {
         // Check the count
         assert_eq!(merged.count(), random_data.len());
     }
+
+    // Tests for Hstats::quantile / percentile / median
+    #[test]
+    fn test_quantile() {
+        let mut hstats = Hstats::new(0.0, 10.0, 10);
+        for v in 0..10 {
+            hstats.add(v as f64);
+        }
+
+        assert_eq!(hstats.quantile(0.0), 0.0);
+        assert_eq!(hstats.quantile(1.0), 10.0);
+        assert!(hstats.quantile(0.5).approx_eq(5.0, (1.0e-9, 2)));
+    }
+
+    #[test]
+    fn test_percentile_and_median() {
+        let mut hstats = Hstats::new(0.0, 10.0, 10);
+        for v in 0..10 {
+            hstats.add(v as f64);
+        }
+
+        assert_eq!(hstats.percentile(0.0), hstats.quantile(0.0));
+        assert_eq!(hstats.percentile(100.0), hstats.quantile(1.0));
+        assert_eq!(hstats.median(), hstats.quantile(0.5));
+    }
+
+    #[test]
+    fn test_quantile_underflow_and_overflow() {
+        let mut hstats = Hstats::new(0.0, 10.0, 10);
+        hstats.add(-5.0); // underflow
+        hstats.add(15.0); // overflow
+
+        // The rank for q=0 falls inside the underflow bucket.
+        assert_eq!(hstats.quantile(0.0), hstats.start());
+        // The rank for q=1 falls inside the overflow bucket.
+        assert_eq!(hstats.quantile(1.0), hstats.end());
+    }
+
+    #[test]
+    #[should_panic(expected = "q (1.5) must be in the range [0, 1]")]
+    fn test_quantile_out_of_range() {
+        let hstats = Hstats::new(0.0, 10.0, 10);
+        let _ = hstats.quantile(1.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "p (101) must be in the range [0, 100]")]
+    fn test_percentile_out_of_range() {
+        let hstats = Hstats::new(0.0, 10.0, 10);
+        let _ = hstats.percentile(101.0);
+    }
+
+    // Tests for Hstats::log_scale
+    #[test]
+    fn test_log_scale() {
+        let hstats = Hstats::log_scale(1.0, 1000.0, 2);
+
+        // 2 * 10^2 = 200, rounded up to the next power of two: 256.
+        let sub_buckets = 256;
+        // ceil(log2(1000.0 / 1.0)) = 10 magnitudes.
+        let magnitudes = 10;
+
+        assert_eq!(hstats.bin_count(), magnitudes * sub_buckets);
+        assert_eq!(hstats.start(), 1.0);
+        assert_eq!(hstats.end(), 1024.0);
+    }
+
+    #[test]
+    fn test_log_scale_add_and_quantile() {
+        let mut hstats = Hstats::log_scale(1.0, 1_000_000.0, 3);
+
+        for exponent in 0..6 {
+            hstats.add(10f64.powi(exponent));
+        }
+
+        assert_eq!(hstats.count(), 6);
+        assert_eq!(hstats.underflow, 0);
+        assert_eq!(hstats.overflow, 0);
+
+        // Values land in fine-grained, well-separated buckets, so the median
+        // (the rank-3 value out of 6, i.e. 100) should come back close to its
+        // true value despite only being reconstructed from bin counts.
+        let median = hstats.median();
+        assert!((median - 100.0).abs() / 100.0 < 1.0e-2);
+    }
+
+    #[test]
+    #[should_panic(expected = "lowest_discernible (0) must be greater than 0")]
+    fn test_log_scale_lowest_discernible_not_positive() {
+        let _ = Hstats::log_scale(0.0, 1000.0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "highest (1) must be greater than lowest_discernible (1)")]
+    fn test_log_scale_highest_not_greater() {
+        let _ = Hstats::log_scale(1.0, 1.0, 2);
+    }
+
+    #[test]
+    fn test_log_scale_merge() {
+        let mut hstats1 = Hstats::log_scale(1.0, 1000.0, 2);
+        hstats1.add(5.0);
+        let mut hstats2 = Hstats::log_scale(1.0, 1000.0, 2);
+        hstats2.add(500.0);
+
+        let merged = hstats1.merge(&hstats2);
+        assert_eq!(merged.count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bin edges must be equal")]
+    fn test_log_scale_merge_different_config() {
+        let hstats1 = Hstats::log_scale(1.0, 1000.0, 2);
+        let hstats2 = Hstats::log_scale(1.0, 1000.0, 3);
+
+        let _ = hstats1.merge(&hstats2);
+    }
+
+    #[test]
+    #[should_panic(expected = "sig_figs (19) must be at most 18")]
+    fn test_log_scale_sig_figs_too_large() {
+        let _ = Hstats::log_scale(1.0, 1000.0, 19);
+    }
+
+    #[test]
+    #[should_panic(expected = "spans too many magnitudes")]
+    fn test_log_scale_too_many_magnitudes() {
+        // 30 orders of magnitude: a latency/size range wide enough to overflow
+        // the `1u64 << magnitude` shift if it isn't bounded first.
+        let _ = Hstats::log_scale(1.0e-15, 1.0e15, 2);
+    }
+
+    // Test for the `serde` feature
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut hstats = Hstats::new(0.0, 10.0, 10);
+        hstats.add(1.0);
+        hstats.add(5.0);
+        hstats.add(-1.0);
+        hstats.add(11.0);
+
+        let serialized = serde_json::to_string(&hstats).unwrap();
+        let deserialized: Hstats<f64> = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.bins, hstats.bins);
+        assert_eq!(deserialized.underflow, hstats.underflow);
+        assert_eq!(deserialized.overflow, hstats.overflow);
+        assert_eq!(deserialized.count(), hstats.count());
+        assert_eq!(deserialized.mean(), hstats.mean());
+        assert_eq!(deserialized.std_dev(), hstats.std_dev());
+        assert_eq!(deserialized.min(), hstats.min());
+        assert_eq!(deserialized.max(), hstats.max());
+
+        // A round-tripped histogram should merge just like the original.
+        let mut other = Hstats::new(0.0, 10.0, 10);
+        other.add(2.0);
+
+        let merged_original = hstats.merge(&other);
+        let merged_deserialized = deserialized.merge(&other);
+
+        assert_eq!(merged_original.bins, merged_deserialized.bins);
+        assert_eq!(merged_original.count(), merged_deserialized.count());
+    }
+
+    // Tests for Hstats::widths / normalized_bins
+    #[test]
+    fn test_widths_uniform() {
+        let hstats = Hstats::new(0.0, 10.0, 10);
+        let widths: Vec<f64> = hstats.widths().collect();
+
+        assert_eq!(widths.len(), 12);
+        assert!(widths[0].is_infinite());
+        assert!(widths[11].is_infinite());
+        assert!(widths[1..11].iter().all(|&w| w == 1.0));
+    }
+
+    #[test]
+    fn test_widths_non_uniform() {
+        let hstats = Hstats::from_ranges(vec![0.0, 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]);
+        let widths: Vec<f64> = hstats.widths().collect();
+        let expected = [0.1, 0.1, 0.3, 0.2, 0.3, 1.0];
+
+        for (width, expected) in widths[1..7].iter().zip(expected) {
+            assert!(width.approx_eq(expected, (1.0e-9, 2)));
+        }
+    }
+
+    #[test]
+    fn test_normalized_bins() {
+        let mut hstats = Hstats::from_ranges(vec![0.0, 0.1, 0.2, 0.5, 0.7, 1.0, 2.0]);
+        hstats.add(0.05); // bin 0, width 0.1
+        hstats.add(0.6); // bin 3, width 0.2
+        hstats.add(0.65); // bin 3, width 0.2
+        hstats.add(-1.0); // underflow
+        hstats.add(2.0); // overflow
+
+        let normalized: Vec<f64> = hstats.normalized_bins().collect();
+
+        assert_eq!(normalized[0], 0.0); // underflow density is zero
+        assert!(normalized[1].approx_eq(1.0 / 0.1, (1.0e-9, 2))); // 1 sample in a 0.1-wide bin
+        assert!(normalized[4].approx_eq(2.0 / 0.2, (1.0e-9, 2))); // 2 samples in a 0.2-wide bin
+        assert_eq!(normalized[7], 0.0); // overflow density is zero
+    }
+
+    // Tests for Hstats::with_buckets
+    #[test]
+    fn test_with_buckets_starts_within_placeholder_range() {
+        let hstats: Hstats<f64> = Hstats::with_buckets(10, 3);
+
+        assert_eq!(hstats.start(), 0.0);
+        assert_eq!(hstats.end(), 1.0);
+        assert_eq!(hstats.bin_count(), 10);
+    }
+
+    #[test]
+    fn test_with_buckets_rescales_on_overflow() {
+        let mut hstats: Hstats<f64> = Hstats::with_buckets(10, 2);
+
+        hstats.add(0.5);
+        hstats.add(5.0); // outside the initial [0, 1) range
+
+        // The range grew to include 5.0 without ever recording an overflow.
+        assert!(hstats.end() > 5.0);
+        assert_eq!(hstats.overflow, 0);
+        assert_eq!(hstats.count(), 2);
+    }
+
+    #[test]
+    fn test_with_buckets_rescales_on_underflow() {
+        let mut hstats: Hstats<f64> = Hstats::with_buckets(10, 2);
+
+        hstats.add(0.5);
+        hstats.add(-5.0); // outside the initial [0, 1) range
+
+        // The range grew to include -5.0 without ever recording an underflow.
+        assert!(hstats.start() <= -5.0);
+        assert_eq!(hstats.underflow, 0);
+        assert_eq!(hstats.count(), 2);
+    }
+
+    #[test]
+    fn test_with_buckets_merge_rescales_to_common_range() {
+        let mut hstats1: Hstats<f64> = Hstats::with_buckets(10, 2);
+        hstats1.add(0.5);
+        hstats1.add(5.0);
+
+        let mut hstats2 = Hstats::with_buckets(10, 2);
+        hstats2.add(0.5);
+        hstats2.add(50.0);
+
+        let merged = hstats1.merge(&hstats2);
+
+        assert!(merged.end() >= 50.0);
+        assert_eq!(merged.underflow, 0);
+        assert_eq!(merged.overflow, 0);
+        assert_eq!(merged.count(), 4);
+    }
+
+    #[test]
+    fn test_with_buckets_rescale_conserves_bin_sum() {
+        let mut hstats: Hstats<f64> = Hstats::with_buckets(10, 2);
+
+        // The growing values repeatedly push past the end of the range, forcing
+        // several rescales and repeatedly exercising `redistribute`'s rounding.
+        for i in 0..100 {
+            hstats.add(i as f64 * 0.37);
+        }
+
+        let bin_sum: u64 = hstats.bins.iter().sum::<u64>() + hstats.underflow + hstats.overflow;
+        assert_eq!(bin_sum, 100);
+    }
+
+    #[test]
+    fn test_with_buckets_merge_conserves_bin_sum() {
+        let mut hstats1: Hstats<f64> = Hstats::with_buckets(10, 2);
+        for i in 0..50 {
+            hstats1.add(i as f64 * 0.9);
+        }
+
+        let mut hstats2: Hstats<f64> = Hstats::with_buckets(10, 2);
+        for i in 0..50 {
+            hstats2.add(-(i as f64) * 2.3);
+        }
+
+        let merged = hstats1.merge(&hstats2);
+
+        let bin_sum: u64 = merged.bins.iter().sum::<u64>() + merged.underflow + merged.overflow;
+        assert_eq!(bin_sum, 100);
+    }
 }
@@ -0,0 +1,5 @@
+extern crate alloc;
+
+mod hstats;
+
+pub use hstats::Hstats;